@@ -1,16 +1,27 @@
 //! This crate provides a [`rustls::crypto::CryptoProvider`] that includes
-//! a hybrid[^1], post-quantum-secure[^2] key exchange algorithm --
-//! specifically [X25519MLKEM768].
+//! hybrid[^1], post-quantum-secure[^2] key exchange algorithms --
+//! specifically [X25519MLKEM768], [SecP256r1MLKEM768],
+//! [SecP384r1MLKEM1024], and [X-Wing].
 //!
-//! X25519MLKEM768 is pre-standardization, so you should treat
+//! These are pre-standardization, so you should treat
 //! this as experimental.  You may see unexpected interop failures, and
-//! the algorithm implemented here may not be the one that eventually
-//! becomes widely deployed.
+//! the algorithms implemented here may not be the ones that eventually
+//! become widely deployed.
 //!
-//! However, the two components of this key exchange are well regarded:
-//! X25519 alone is already used by default by rustls, and tends to have
-//! higher quality implementations than other elliptic curves.
-//! ML-KEM-768 was standardized by NIST in [FIPS203].
+//! However, the components of these key exchanges are well regarded:
+//! X25519, secp256r1 and secp384r1 are already used by default by rustls,
+//! and tend to have higher quality implementations than other elliptic
+//! curves.  ML-KEM-768 and ML-KEM-1024 were standardized by NIST in
+//! [FIPS203].  X-Wing uses the same X25519 and ML-KEM-768 primitives as
+//! `X25519MLKEM768`, but combines them with a SHA3-256 KDF rather than
+//! plain concatenation, so its security does not depend on the TLS
+//! transcript for domain separation.
+//!
+//! By default, all four groups are built on [`HybridKxGroup`], a generic
+//! hybrid classical/ML-KEM group (`X25519MLKEM768` as a thin wrapper around
+//! one, the others as direct instantiations) that downstream crates can
+//! also instantiate themselves, to experiment with combinations this crate
+//! does not provide.
 //!
 //! [^1]: meaning: a construction that runs a classical and post-quantum
 //!       key exchange, and uses the output of both together.  This is a hedge
@@ -23,6 +34,9 @@
 //!       now and attacked later.
 //!
 //! [X25519MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+//! [SecP256r1MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+//! [SecP384r1MLKEM1024]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+//! [X-Wing]: <https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/>
 //! [FIPS203]: <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf>
 //!
 //! # How to use this crate
@@ -35,7 +49,7 @@
 //! rustls_post_quantum::provider().install_default().unwrap();
 //! ```
 //!
-//! **To incorporate just the key exchange algorithm in a custom [`rustls::crypto::CryptoProvider`]**:
+//! **To incorporate just the key exchange algorithms in a custom [`rustls::crypto::CryptoProvider`]**:
 //!
 //! ```rust
 //! use rustls::crypto::{aws_lc_rs, CryptoProvider};
@@ -49,73 +63,149 @@
 //! };
 //! ```
 //!
+//! # Cargo features
+//!
+//! By default, this crate uses aws-lc-rs for both the ML-KEM and classical
+//! halves of each key exchange.  The `ml-kem-pure` feature swaps
+//! `X25519MLKEM768`'s ML-KEM half for the pure-Rust, `no_std` [`ml-kem`]
+//! crate, and its classical half for rustls's `ring` provider, so that this
+//! key exchange can be used on targets that cannot take on aws-lc-rs's C and
+//! assembly code. With this feature enabled, aws-lc-rs is not referenced at
+//! all: [`SecP256r1MLKEM768`], [`SecP384r1MLKEM1024`], [`XWing`], and the
+//! [`mlkem768`] module (which are all built directly on aws-lc-rs) are not
+//! available.
+//!
+//! [`ml-kem`]: <https://docs.rs/ml-kem>
+//!
 
+#[cfg(not(feature = "ml-kem-pure"))]
 use aws_lc_rs::kem;
-use aws_lc_rs::unstable::kem::ML_KEM_768;
+#[cfg(not(feature = "ml-kem-pure"))]
+use aws_lc_rs::unstable::kem::{ML_KEM_1024, ML_KEM_768};
+#[cfg(not(feature = "ml-kem-pure"))]
 use rustls::crypto::aws_lc_rs::{default_provider, kx_group};
 use rustls::crypto::{
     ActiveKeyExchange, CompletedKeyExchange, CryptoProvider, SharedSecret, SupportedKxGroup,
 };
 use rustls::ffdhe_groups::FfdheGroup;
 use rustls::{Error, NamedGroup, PeerMisbehaved, ProtocolVersion};
+#[cfg(not(feature = "ml-kem-pure"))]
+use sha3::{Digest, Sha3_256};
 
-/// A `CryptoProvider` which includes `X25519MLKEM768` key exchange.
+/// A `CryptoProvider` which includes `X25519MLKEM768`, `SecP256r1MLKEM768`,
+/// `SecP384r1MLKEM1024` and `XWing` key exchange.
+///
+/// With the `ml-kem-pure` feature enabled, this is instead built on
+/// [`rustls::crypto::ring::default_provider`] and only includes
+/// `X25519MLKEM768`, as the other groups have no pure-Rust backend.
+#[cfg(not(feature = "ml-kem-pure"))]
 pub fn provider() -> CryptoProvider {
     let mut parent = default_provider();
+    parent.kx_groups.insert(0, &X25519MLKEM768);
+    parent.kx_groups.insert(1, &SecP256r1MLKEM768);
+    parent.kx_groups.insert(2, &SecP384r1MLKEM1024);
+    parent.kx_groups.insert(3, &XWing);
     parent
-        .kx_groups
-        .insert(0, &X25519MLKEM768);
+}
+
+#[cfg(feature = "ml-kem-pure")]
+pub fn provider() -> CryptoProvider {
+    let mut parent = rustls::crypto::ring::default_provider();
+    parent.kx_groups.insert(0, &X25519MLKEM768);
     parent
 }
 
-/// This is the [X25519MLKEM768] key exchange.
+/// A generic hybrid classical/ML-KEM key exchange group.
 ///
-/// [X25519MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
-#[derive(Debug)]
-pub struct X25519MLKEM768;
+/// This combines any rustls [`SupportedKxGroup`] (the classical half) with
+/// any aws-lc-rs ML-KEM parameter set (the post-quantum half), under a TLS
+/// codepoint, share ordering and secret combiner of the caller's choosing.
+/// [`SecP256r1MLKEM768`], [`SecP384r1MLKEM1024`] and [`XWing`] are all
+/// `static` instantiations of this type (and [`X25519MLKEM768`] is a thin
+/// wrapper around one); downstream crates can define their own
+/// not-yet-standardized combinations the same way, without forking this
+/// crate.
+#[cfg(not(feature = "ml-kem-pure"))]
+#[derive(Clone, Copy)]
+pub struct HybridKxGroup {
+    /// The TLS codepoint this group is negotiated under.
+    pub name: NamedGroup,
+    /// The classical half of the key exchange.
+    pub classical: &'static dyn SupportedKxGroup,
+    /// The length, in bytes, of the classical half's public share.
+    pub classical_share_len: usize,
+    /// The ML-KEM parameter set used for the post-quantum half.
+    pub ml_kem: &'static kem::Algorithm<kem::AlgorithmId>,
+    /// The length, in bytes, of the ML-KEM encapsulation (public) key.
+    pub ml_kem_encap_len: usize,
+    /// The length, in bytes, of the ML-KEM ciphertext.
+    pub ml_kem_ciphertext_len: usize,
+    /// The order the ML-KEM and classical shares are concatenated in.
+    pub share_order: ShareOrder,
+    /// How the classical and ML-KEM shared secrets are combined.
+    pub combiner: Combiner,
+}
 
-impl SupportedKxGroup for X25519MLKEM768 {
-    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, Error> {
-        let x25519 = kx_group::X25519.start()?;
+#[cfg(not(feature = "ml-kem-pure"))]
+impl std::fmt::Debug for HybridKxGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HybridKxGroup")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
 
-        let ml_kem = kem::DecapsulationKey::generate(&ML_KEM_768)
-            .map_err(|_| Error::FailedToGetRandomBytes)?;
+#[cfg(not(feature = "ml-kem-pure"))]
+impl SupportedKxGroup for HybridKxGroup {
+    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, Error> {
+        let classical = self.classical.start()?;
 
+        let ml_kem =
+            kem::DecapsulationKey::generate(self.ml_kem).map_err(|_| Error::FailedToGetRandomBytes)?;
         let ml_kem_pub = ml_kem
             .encapsulation_key()
             .map_err(|_| Error::FailedToGetRandomBytes)?;
 
-        let mut combined_pub_key = Vec::with_capacity(COMBINED_PUBKEY_LEN);
-        combined_pub_key.extend_from_slice(ml_kem_pub.key_bytes().unwrap().as_ref());
-        combined_pub_key.extend_from_slice(x25519.pub_key());
+        let classical_pub_key = classical.pub_key().to_vec();
+        let combined_pub_key = self
+            .share_order
+            .combine(ml_kem_pub.key_bytes().unwrap().as_ref(), &classical_pub_key);
 
-        Ok(Box::new(Active {
-            x25519,
+        Ok(Box::new(HybridActive {
+            group: *self,
+            classical,
             decap_key: Box::new(ml_kem),
             combined_pub_key,
+            classical_pub_key,
         }))
     }
 
     fn start_and_complete(&self, client_share: &[u8]) -> Result<CompletedKeyExchange, Error> {
-        let Some(share) = ReceivedShare::new(client_share) else {
+        let Some(share) = ReceivedShare::new(client_share, self) else {
             return Err(INVALID_KEY_SHARE);
         };
 
-        let x25519 = kx_group::X25519.start_and_complete(share.x25519)?;
+        let classical = self
+            .classical
+            .start_and_complete(share.classical)?;
 
-        let (ml_kem_share, ml_kem_secret) = kem::EncapsulationKey::new(&ML_KEM_768, share.ml_kem)
+        let (ml_kem_share, ml_kem_secret) = kem::EncapsulationKey::new(self.ml_kem, share.ml_kem)
             .map_err(|_| INVALID_KEY_SHARE)
-            .and_then(|pk| {
-                pk.encapsulate()
-                    .map_err(|_| INVALID_KEY_SHARE)
-            })?;
+            .and_then(|pk| pk.encapsulate().map_err(|_| INVALID_KEY_SHARE))?;
 
-        let combined_secret = CombinedSecret::combine(x25519.secret, ml_kem_secret);
-        let combined_share = CombinedShare::combine(&x25519.pub_key, ml_kem_share);
+        let combined_secret = self.combiner.combine(
+            ml_kem_secret.as_ref(),
+            classical.secret.secret_bytes(),
+            &classical.pub_key,
+            share.classical,
+        );
+        let combined_share = self
+            .share_order
+            .combine(ml_kem_share.as_ref(), &classical.pub_key);
 
         Ok(CompletedKeyExchange {
-            group: self.name(),
-            pub_key: combined_share.0,
+            group: self.name,
+            pub_key: combined_share,
             secret: SharedSecret::from(&combined_secret.0[..]),
         })
     }
@@ -125,7 +215,7 @@ impl SupportedKxGroup for X25519MLKEM768 {
     }
 
     fn name(&self) -> NamedGroup {
-        NAMED_GROUP
+        self.name
     }
 
     fn usable_for_version(&self, version: ProtocolVersion) -> bool {
@@ -133,24 +223,33 @@ impl SupportedKxGroup for X25519MLKEM768 {
     }
 }
 
-struct Active {
-    x25519: Box<dyn ActiveKeyExchange>,
+#[cfg(not(feature = "ml-kem-pure"))]
+struct HybridActive {
+    group: HybridKxGroup,
+    classical: Box<dyn ActiveKeyExchange>,
     decap_key: Box<kem::DecapsulationKey<kem::AlgorithmId>>,
     combined_pub_key: Vec<u8>,
+    classical_pub_key: Vec<u8>,
 }
 
-impl ActiveKeyExchange for Active {
+#[cfg(not(feature = "ml-kem-pure"))]
+impl ActiveKeyExchange for HybridActive {
     fn complete(self: Box<Self>, peer_pub_key: &[u8]) -> Result<SharedSecret, Error> {
-        let Some(ciphertext) = ReceivedCiphertext::new(peer_pub_key) else {
+        let Some(ciphertext) = ReceivedCiphertext::new(peer_pub_key, &self.group) else {
             return Err(INVALID_KEY_SHARE);
         };
 
-        let combined = CombinedSecret::combine(
-            self.x25519
-                .complete(ciphertext.x25519)?,
-            self.decap_key
-                .decapsulate(ciphertext.ml_kem.into())
-                .map_err(|_| INVALID_KEY_SHARE)?,
+        let classical_secret = self.classical.complete(ciphertext.classical)?;
+        let ml_kem_secret = self
+            .decap_key
+            .decapsulate(ciphertext.ml_kem.into())
+            .map_err(|_| INVALID_KEY_SHARE)?;
+
+        let combined = self.group.combiner.combine(
+            ml_kem_secret.as_ref(),
+            classical_secret.secret_bytes(),
+            ciphertext.classical,
+            &self.classical_pub_key,
         );
         Ok(SharedSecret::from(&combined.0[..]))
     }
@@ -164,72 +263,720 @@ impl ActiveKeyExchange for Active {
     }
 
     fn group(&self) -> NamedGroup {
-        NAMED_GROUP
+        self.group.name
+    }
+}
+
+/// The order in which the ML-KEM and classical shares are concatenated.
+///
+/// The ecdhe-mlkem draft is not consistent here: the ML-KEM share comes
+/// first for `X25519MLKEM768`, but second for the secp256r1 and secp384r1
+/// variants.
+#[derive(Clone, Copy, Debug)]
+pub enum ShareOrder {
+    /// The ML-KEM share or ciphertext comes first, then the classical one.
+    MlKemFirst,
+    /// The classical share comes first, then the ML-KEM one or ciphertext.
+    ClassicalFirst,
+}
+
+impl ShareOrder {
+    fn combine(&self, ml_kem: &[u8], classical: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(ml_kem.len() + classical.len());
+        match self {
+            Self::MlKemFirst => {
+                out.extend_from_slice(ml_kem);
+                out.extend_from_slice(classical);
+            }
+            Self::ClassicalFirst => {
+                out.extend_from_slice(classical);
+                out.extend_from_slice(ml_kem);
+            }
+        }
+        out
+    }
+
+    fn split<'a>(&self, buf: &'a [u8], ml_kem_len: usize) -> (&'a [u8], &'a [u8]) {
+        match self {
+            Self::MlKemFirst => buf.split_at(ml_kem_len),
+            Self::ClassicalFirst => {
+                let (classical, ml_kem) = buf.split_at(buf.len() - ml_kem_len);
+                (ml_kem, classical)
+            }
+        }
+    }
+}
+
+/// How a hybrid group's classical and ML-KEM shared secrets are combined
+/// into the final TLS shared secret.
+#[cfg(not(feature = "ml-kem-pure"))]
+#[derive(Clone, Copy, Debug)]
+pub enum Combiner {
+    /// Concatenate the raw shared secrets, as `ml_kem || classical`.
+    Concatenate,
+    /// Combine as in [X-Wing]: a SHA3-256 KDF over both shared secrets, the
+    /// classical ciphertext, and the classical public key.
+    ///
+    /// [X-Wing]: <https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/>
+    XWing,
+}
+
+#[cfg(not(feature = "ml-kem-pure"))]
+impl Combiner {
+    fn combine(
+        &self,
+        ml_kem_secret: &[u8],
+        classical_secret: &[u8],
+        classical_ciphertext: &[u8],
+        classical_pub_key: &[u8],
+    ) -> CombinedSecret {
+        match self {
+            Self::Concatenate => CombinedSecret::combine(classical_secret, ml_kem_secret),
+            Self::XWing => xwing_combine(
+                ml_kem_secret,
+                classical_secret,
+                classical_ciphertext,
+                classical_pub_key,
+            ),
+        }
     }
 }
 
+/// The fixed 6-byte domain separator prepended to every X-Wing combiner
+/// input: the bytes of the ASCII string `\.//^\`.
+#[cfg(not(feature = "ml-kem-pure"))]
+const XWING_LABEL: &[u8] = &[0x5c, 0x2e, 0x2f, 0x2f, 0x5e, 0x5c];
+
+/// Derives the X-Wing shared secret as
+/// `SHA3-256(XWingLabel || ss_mlkem || ss_classical || ct_classical || pk_classical)`.
+#[cfg(not(feature = "ml-kem-pure"))]
+fn xwing_combine(
+    ss_mlkem: &[u8],
+    ss_classical: &[u8],
+    ct_classical: &[u8],
+    pk_classical: &[u8],
+) -> CombinedSecret {
+    let mut hasher = Sha3_256::new();
+    hasher.update(XWING_LABEL);
+    hasher.update(ss_mlkem);
+    hasher.update(ss_classical);
+    hasher.update(ct_classical);
+    hasher.update(pk_classical);
+    CombinedSecret(hasher.finalize().to_vec())
+}
+
+#[cfg(not(feature = "ml-kem-pure"))]
 struct ReceivedShare<'a> {
     ml_kem: &'a [u8],
-    x25519: &'a [u8],
+    classical: &'a [u8],
 }
 
+#[cfg(not(feature = "ml-kem-pure"))]
 impl<'a> ReceivedShare<'a> {
-    fn new(buf: &'a [u8]) -> Option<ReceivedShare<'a>> {
-        if buf.len() != COMBINED_PUBKEY_LEN {
+    fn new(buf: &'a [u8], group: &HybridKxGroup) -> Option<ReceivedShare<'a>> {
+        if buf.len() != group.ml_kem_encap_len + group.classical_share_len {
             return None;
         }
 
-        let (ml_kem, x25519) = buf.split_at(MLKEM768_ENCAP_LEN);
-        Some(ReceivedShare { ml_kem, x25519 })
+        let (ml_kem, classical) = group.share_order.split(buf, group.ml_kem_encap_len);
+        Some(ReceivedShare { ml_kem, classical })
     }
 }
 
+#[cfg(not(feature = "ml-kem-pure"))]
 struct ReceivedCiphertext<'a> {
     ml_kem: &'a [u8],
-    x25519: &'a [u8],
+    classical: &'a [u8],
 }
 
+#[cfg(not(feature = "ml-kem-pure"))]
 impl<'a> ReceivedCiphertext<'a> {
-    fn new(buf: &'a [u8]) -> Option<ReceivedCiphertext<'a>> {
-        if buf.len() != COMBINED_CIPHERTEXT_LEN {
+    fn new(buf: &'a [u8], group: &HybridKxGroup) -> Option<ReceivedCiphertext<'a>> {
+        if buf.len() != group.ml_kem_ciphertext_len + group.classical_share_len {
             return None;
         }
 
-        let (ml_kem, x25519) = buf.split_at(MLKEM768_CIPHERTEXT_LEN);
-        Some(ReceivedCiphertext { ml_kem, x25519 })
+        let (ml_kem, classical) = group.share_order.split(buf, group.ml_kem_ciphertext_len);
+        Some(ReceivedCiphertext { ml_kem, classical })
     }
 }
 
-struct CombinedSecret([u8; COMBINED_SHARED_SECRET_LEN]);
+struct CombinedSecret(Vec<u8>);
 
 impl CombinedSecret {
-    fn combine(x25519: SharedSecret, ml_kem: kem::SharedSecret) -> Self {
-        let mut out = CombinedSecret([0u8; COMBINED_SHARED_SECRET_LEN]);
-        out.0[..MLKEM768_SECRET_LEN].copy_from_slice(ml_kem.as_ref());
-        out.0[MLKEM768_SECRET_LEN..].copy_from_slice(x25519.secret_bytes());
-        out
-    }
-}
-
-struct CombinedShare(Vec<u8>);
-
-impl CombinedShare {
-    fn combine(x25519: &[u8], ml_kem: kem::Ciphertext<'_>) -> Self {
-        let mut out = CombinedShare(vec![0u8; COMBINED_CIPHERTEXT_LEN]);
-        out.0[..MLKEM768_CIPHERTEXT_LEN].copy_from_slice(ml_kem.as_ref());
-        out.0[MLKEM768_CIPHERTEXT_LEN..].copy_from_slice(x25519);
-        out
+    fn combine(classical: &[u8], ml_kem: &[u8]) -> Self {
+        let mut out = Vec::with_capacity(ml_kem.len() + classical.len());
+        out.extend_from_slice(ml_kem);
+        out.extend_from_slice(classical);
+        Self(out)
     }
 }
 
-const NAMED_GROUP: NamedGroup = NamedGroup::Unknown(0x11ec);
-
 const INVALID_KEY_SHARE: Error = Error::PeerMisbehaved(PeerMisbehaved::InvalidKeyShare);
 
 const X25519_LEN: usize = 32;
+#[cfg(not(feature = "ml-kem-pure"))]
+const SECP256R1_LEN: usize = 65;
+#[cfg(not(feature = "ml-kem-pure"))]
+const SECP384R1_LEN: usize = 97;
+
 const MLKEM768_CIPHERTEXT_LEN: usize = 1088;
 const MLKEM768_ENCAP_LEN: usize = 1184;
+
+#[cfg(not(feature = "ml-kem-pure"))]
+const MLKEM1024_CIPHERTEXT_LEN: usize = 1568;
+#[cfg(not(feature = "ml-kem-pure"))]
+const MLKEM1024_ENCAP_LEN: usize = 1568;
+
 const MLKEM768_SECRET_LEN: usize = 32;
-const COMBINED_PUBKEY_LEN: usize = MLKEM768_ENCAP_LEN + X25519_LEN;
-const COMBINED_CIPHERTEXT_LEN: usize = MLKEM768_CIPHERTEXT_LEN + X25519_LEN;
-const COMBINED_SHARED_SECRET_LEN: usize = MLKEM768_SECRET_LEN + X25519_LEN;
+
+#[cfg(not(feature = "ml-kem-pure"))]
+static X25519_MLKEM768_GROUP: HybridKxGroup = HybridKxGroup {
+    name: NamedGroup::Unknown(0x11ec),
+    classical: kx_group::X25519,
+    classical_share_len: X25519_LEN,
+    ml_kem: &ML_KEM_768,
+    ml_kem_encap_len: MLKEM768_ENCAP_LEN,
+    ml_kem_ciphertext_len: MLKEM768_CIPHERTEXT_LEN,
+    share_order: ShareOrder::MlKemFirst,
+    combiner: Combiner::Concatenate,
+};
+
+/// This is the [X25519MLKEM768] key exchange.
+///
+/// This is a thin wrapper around a [`HybridKxGroup`] instantiation, kept as
+/// its own unit struct (rather than a `pub static HybridKxGroup`) so that
+/// this type's shape does not change under the `ml-kem-pure` feature, where
+/// `X25519MLKEM768` must instead be backed by the pure-Rust `ml-kem` crate.
+///
+/// [X25519MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+#[cfg(not(feature = "ml-kem-pure"))]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct X25519MLKEM768;
+
+#[cfg(not(feature = "ml-kem-pure"))]
+impl SupportedKxGroup for X25519MLKEM768 {
+    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, Error> {
+        X25519_MLKEM768_GROUP.start()
+    }
+
+    fn start_and_complete(&self, client_share: &[u8]) -> Result<CompletedKeyExchange, Error> {
+        X25519_MLKEM768_GROUP.start_and_complete(client_share)
+    }
+
+    fn ffdhe_group(&self) -> Option<FfdheGroup<'static>> {
+        None
+    }
+
+    fn name(&self) -> NamedGroup {
+        X25519_MLKEM768_GROUP.name
+    }
+
+    fn usable_for_version(&self, version: ProtocolVersion) -> bool {
+        version == ProtocolVersion::TLSv1_3
+    }
+}
+
+/// This is the [SecP256r1MLKEM768] key exchange.
+///
+/// [SecP256r1MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+#[cfg(not(feature = "ml-kem-pure"))]
+#[allow(non_upper_case_globals)]
+pub static SecP256r1MLKEM768: HybridKxGroup = HybridKxGroup {
+    name: NamedGroup::Unknown(0x11eb),
+    classical: kx_group::SECP256R1,
+    classical_share_len: SECP256R1_LEN,
+    ml_kem: &ML_KEM_768,
+    ml_kem_encap_len: MLKEM768_ENCAP_LEN,
+    ml_kem_ciphertext_len: MLKEM768_CIPHERTEXT_LEN,
+    share_order: ShareOrder::ClassicalFirst,
+    combiner: Combiner::Concatenate,
+};
+
+/// This is the [SecP384r1MLKEM1024] key exchange.
+///
+/// [SecP384r1MLKEM1024]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+#[cfg(not(feature = "ml-kem-pure"))]
+#[allow(non_upper_case_globals)]
+pub static SecP384r1MLKEM1024: HybridKxGroup = HybridKxGroup {
+    name: NamedGroup::Unknown(0x11ed),
+    classical: kx_group::SECP384R1,
+    classical_share_len: SECP384R1_LEN,
+    ml_kem: &ML_KEM_1024,
+    ml_kem_encap_len: MLKEM1024_ENCAP_LEN,
+    ml_kem_ciphertext_len: MLKEM1024_CIPHERTEXT_LEN,
+    share_order: ShareOrder::ClassicalFirst,
+    combiner: Combiner::Concatenate,
+};
+
+/// This is the [X-Wing] hybrid key exchange.
+///
+/// Unlike `X25519MLKEM768`, which derives its shared secret by simply
+/// concatenating the ML-KEM and X25519 secrets, X-Wing derives it with a
+/// SHA3-256 KDF that also binds in the X25519 ciphertext and public key.
+/// This means the combiner itself provides domain separation, rather than
+/// relying on the TLS transcript to do so.
+///
+/// [X-Wing]: <https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/>
+#[cfg(not(feature = "ml-kem-pure"))]
+#[allow(non_upper_case_globals)]
+pub static XWing: HybridKxGroup = HybridKxGroup {
+    name: NamedGroup::Unknown(0x647a),
+    classical: kx_group::X25519,
+    classical_share_len: X25519_LEN,
+    ml_kem: &ML_KEM_768,
+    ml_kem_encap_len: MLKEM768_ENCAP_LEN,
+    ml_kem_ciphertext_len: MLKEM768_CIPHERTEXT_LEN,
+    share_order: ShareOrder::MlKemFirst,
+    combiner: Combiner::XWing,
+};
+
+/// The pure-Rust, `no_std`-backend variant of [X25519MLKEM768], used when
+/// the `ml-kem-pure` feature is enabled.
+///
+/// This bypasses [`HybridKxGroup`] (which is tied to aws-lc-rs's ML-KEM
+/// implementation) and instead runs X25519 via rustls's `ring` provider and
+/// ML-KEM-768 via the [`ml-kem`] crate.
+///
+/// [X25519MLKEM768]: <https://datatracker.ietf.org/doc/draft-kwiatkowski-tls-ecdhe-mlkem/>
+/// [`ml-kem`]: <https://docs.rs/ml-kem>
+#[cfg(feature = "ml-kem-pure")]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct X25519MLKEM768;
+
+#[cfg(feature = "ml-kem-pure")]
+impl SupportedKxGroup for X25519MLKEM768 {
+    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, Error> {
+        let classical = rustls::crypto::ring::kx_group::X25519.start()?;
+        let (decap_key, ml_kem_pub) = DefaultMlKem768::generate()?;
+        let combined_pub_key = ShareOrder::MlKemFirst.combine(&ml_kem_pub, classical.pub_key());
+
+        Ok(Box::new(X25519Active {
+            classical,
+            decap_key,
+            combined_pub_key,
+        }))
+    }
+
+    fn start_and_complete(&self, client_share: &[u8]) -> Result<CompletedKeyExchange, Error> {
+        if client_share.len() != MLKEM768_ENCAP_LEN + X25519_LEN {
+            return Err(INVALID_KEY_SHARE);
+        }
+        let (ml_kem_share, x25519_share) = client_share.split_at(MLKEM768_ENCAP_LEN);
+
+        let classical = rustls::crypto::ring::kx_group::X25519.start_and_complete(x25519_share)?;
+        let (ml_kem_share, ml_kem_secret) = DefaultMlKem768::encapsulate(ml_kem_share)?;
+
+        let combined_secret =
+            CombinedSecret::combine(classical.secret.secret_bytes(), &ml_kem_secret);
+        let combined_share =
+            ShareOrder::MlKemFirst.combine(&ml_kem_share, &classical.pub_key);
+
+        Ok(CompletedKeyExchange {
+            group: self.name(),
+            pub_key: combined_share,
+            secret: SharedSecret::from(&combined_secret.0[..]),
+        })
+    }
+
+    fn ffdhe_group(&self) -> Option<FfdheGroup<'static>> {
+        None
+    }
+
+    fn name(&self) -> NamedGroup {
+        NamedGroup::Unknown(0x11ec)
+    }
+
+    fn usable_for_version(&self, version: ProtocolVersion) -> bool {
+        version == ProtocolVersion::TLSv1_3
+    }
+}
+
+#[cfg(feature = "ml-kem-pure")]
+struct X25519Active {
+    classical: Box<dyn ActiveKeyExchange>,
+    decap_key: <DefaultMlKem768 as MlKem768Backend>::DecapsulationKey,
+    combined_pub_key: Vec<u8>,
+}
+
+#[cfg(feature = "ml-kem-pure")]
+impl ActiveKeyExchange for X25519Active {
+    fn complete(self: Box<Self>, peer_pub_key: &[u8]) -> Result<SharedSecret, Error> {
+        if peer_pub_key.len() != MLKEM768_CIPHERTEXT_LEN + X25519_LEN {
+            return Err(INVALID_KEY_SHARE);
+        }
+        let (ml_kem_ciphertext, x25519_ciphertext) = peer_pub_key.split_at(MLKEM768_CIPHERTEXT_LEN);
+
+        let classical_secret = self.classical.complete(x25519_ciphertext)?;
+        let ml_kem_secret = DefaultMlKem768::decapsulate(&self.decap_key, ml_kem_ciphertext)?;
+
+        let combined = CombinedSecret::combine(classical_secret.secret_bytes(), &ml_kem_secret);
+        Ok(SharedSecret::from(&combined.0[..]))
+    }
+
+    fn pub_key(&self) -> &[u8] {
+        &self.combined_pub_key
+    }
+
+    fn ffdhe_group(&self) -> Option<FfdheGroup<'static>> {
+        None
+    }
+
+    fn group(&self) -> NamedGroup {
+        NamedGroup::Unknown(0x11ec)
+    }
+}
+
+/// Abstracts the ML-KEM-768 operations used by the `ml-kem-pure` variant of
+/// `X25519MLKEM768` over its backend. Its only implementation is
+/// `PureMlKem768`, backed by the pure-Rust, `no_std`
+/// [`ml-kem`](https://docs.rs/ml-kem) crate; the default build does not use
+/// this trait.
+#[cfg(feature = "ml-kem-pure")]
+trait MlKem768Backend {
+    type DecapsulationKey;
+
+    fn generate() -> Result<(Self::DecapsulationKey, Vec<u8>), Error>;
+
+    fn encapsulate(encap_key: &[u8]) -> Result<(Vec<u8>, [u8; MLKEM768_SECRET_LEN]), Error>;
+
+    fn decapsulate(
+        decap_key: &Self::DecapsulationKey,
+        ciphertext: &[u8],
+    ) -> Result<[u8; MLKEM768_SECRET_LEN], Error>;
+}
+
+#[cfg(feature = "ml-kem-pure")]
+struct PureMlKem768;
+
+#[cfg(feature = "ml-kem-pure")]
+impl MlKem768Backend for PureMlKem768 {
+    type DecapsulationKey = ml_kem::DecapsulationKey<ml_kem::MlKem768Params>;
+
+    fn generate() -> Result<(Self::DecapsulationKey, Vec<u8>), Error> {
+        use ml_kem::{EncodedSizeUser, KemCore};
+
+        let (decap_key, encap_key) = ml_kem::MlKem768::generate(&mut rand_core::OsRng);
+        Ok((decap_key, encap_key.as_bytes().to_vec()))
+    }
+
+    fn encapsulate(encap_key: &[u8]) -> Result<(Vec<u8>, [u8; MLKEM768_SECRET_LEN]), Error> {
+        use ml_kem::kem::Encapsulate;
+        use ml_kem::{EncapsulationKey, EncodedSizeUser, Encoded, MlKem768Params};
+
+        // `Encoded<EncapsulationKey<_>>` is a fixed-size array type; an
+        // arbitrary-length `&[u8]` needs an explicit, fallible conversion,
+        // not `.into()`.
+        let encoded = Encoded::<EncapsulationKey<MlKem768Params>>::try_from(encap_key)
+            .map_err(|_| INVALID_KEY_SHARE)?;
+        let encap_key = EncapsulationKey::<MlKem768Params>::from_bytes(&encoded);
+
+        let (ciphertext, secret) = encap_key
+            .encapsulate(&mut rand_core::OsRng)
+            .map_err(|_| Error::FailedToGetRandomBytes)?;
+
+        let mut out = [0u8; MLKEM768_SECRET_LEN];
+        out.copy_from_slice(&secret);
+        Ok((ciphertext.to_vec(), out))
+    }
+
+    fn decapsulate(
+        decap_key: &Self::DecapsulationKey,
+        ciphertext: &[u8],
+    ) -> Result<[u8; MLKEM768_SECRET_LEN], Error> {
+        use ml_kem::kem::Decapsulate;
+        use ml_kem::{Ciphertext, MlKem768Params};
+
+        let ciphertext = Ciphertext::<MlKem768Params>::try_from(ciphertext)
+            .map_err(|_| INVALID_KEY_SHARE)?;
+        let secret = decap_key
+            .decapsulate(&ciphertext)
+            .map_err(|_| INVALID_KEY_SHARE)?;
+
+        let mut out = [0u8; MLKEM768_SECRET_LEN];
+        out.copy_from_slice(&secret);
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "ml-kem-pure")]
+type DefaultMlKem768 = PureMlKem768;
+
+#[cfg(all(test, feature = "ml-kem-pure"))]
+mod pure_backend_tests {
+    use super::*;
+
+    #[test]
+    fn x25519_mlkem768_round_trips_on_the_pure_backend() {
+        let initiator = X25519MLKEM768.start().unwrap();
+        let completed = X25519MLKEM768
+            .start_and_complete(initiator.pub_key())
+            .unwrap();
+        let initiator_secret = initiator.complete(&completed.pub_key).unwrap();
+
+        assert_eq!(
+            initiator_secret.secret_bytes(),
+            completed.secret.secret_bytes()
+        );
+    }
+}
+
+/// A standalone ML-KEM-768 API, for applications that want this crate's
+/// ML-KEM-768 wiring outside of a TLS handshake (for example, encrypted
+/// file transfer or session resumption tickets).
+///
+/// This wraps [`aws_lc_rs`]'s `DecapsulationKey`/`EncapsulationKey` in
+/// newtypes implementing the RustCrypto [`kem`](https://docs.rs/kem)
+/// crate's [`Encapsulate`]/[`Decapsulate`] traits, as the `x-wing` crate
+/// and other standalone KEM implementations do.
+///
+/// This is built directly on aws-lc-rs and is not available under the
+/// `ml-kem-pure` feature.
+#[cfg(not(feature = "ml-kem-pure"))]
+pub mod mlkem768 {
+    use ::kem::{Decapsulate, Encapsulate};
+    use aws_lc_rs::kem as aws_kem;
+    use aws_lc_rs::unstable::kem::ML_KEM_768;
+    use rustls::Error;
+
+    use crate::{INVALID_KEY_SHARE, MLKEM768_CIPHERTEXT_LEN, MLKEM768_ENCAP_LEN, MLKEM768_SECRET_LEN};
+
+    /// An ML-KEM-768 decapsulation (private) key.
+    pub struct DecapsulationKey(aws_kem::DecapsulationKey<aws_kem::AlgorithmId>);
+
+    /// An ML-KEM-768 encapsulation (public) key.
+    pub struct EncapsulationKey(aws_kem::EncapsulationKey<aws_kem::AlgorithmId>);
+
+    /// An ML-KEM-768 ciphertext.
+    #[derive(Clone)]
+    pub struct Ciphertext(Vec<u8>);
+
+    /// An ML-KEM-768 shared secret.
+    ///
+    /// The underlying bytes are wrapped in [`zeroize::Zeroizing`] so they
+    /// are wiped from memory when this value is dropped, matching the
+    /// hygiene of [`rustls::crypto::SharedSecret`].
+    pub struct SharedSecret(zeroize::Zeroizing<[u8; MLKEM768_SECRET_LEN]>);
+
+    impl DecapsulationKey {
+        /// Generates a new ML-KEM-768 key pair.
+        pub fn generate() -> Result<Self, Error> {
+            aws_kem::DecapsulationKey::generate(&ML_KEM_768)
+                .map(Self)
+                .map_err(|_| Error::FailedToGetRandomBytes)
+        }
+
+        /// Returns the public encapsulation key corresponding to this key.
+        pub fn encapsulation_key(&self) -> Result<EncapsulationKey, Error> {
+            self.0
+                .encapsulation_key()
+                .map(EncapsulationKey)
+                .map_err(|_| Error::FailedToGetRandomBytes)
+        }
+    }
+
+    impl Decapsulate<Ciphertext, SharedSecret> for DecapsulationKey {
+        type Error = Error;
+
+        fn decapsulate(&self, ciphertext: &Ciphertext) -> Result<SharedSecret, Error> {
+            if ciphertext.0.len() != MLKEM768_CIPHERTEXT_LEN {
+                return Err(INVALID_KEY_SHARE);
+            }
+
+            let secret = self
+                .0
+                .decapsulate(ciphertext.0.as_slice().into())
+                .map_err(|_| INVALID_KEY_SHARE)?;
+
+            let mut out = [0u8; MLKEM768_SECRET_LEN];
+            out.copy_from_slice(secret.as_ref());
+            Ok(SharedSecret(zeroize::Zeroizing::new(out)))
+        }
+    }
+
+    impl Encapsulate<Ciphertext, SharedSecret> for EncapsulationKey {
+        type Error = Error;
+
+        /// Encapsulates against this key, producing a fresh ciphertext and
+        /// shared secret.
+        ///
+        /// `rng` is accepted to satisfy the [`Encapsulate`] trait, but is
+        /// otherwise unused: aws-lc-rs draws its own randomness internally
+        /// during encapsulation and does not expose a way to inject an
+        /// external RNG. A caller supplying a seeded or otherwise
+        /// deterministic RNG for reproducible output will not see it take
+        /// effect.
+        fn encapsulate(
+            &self,
+            _rng: &mut impl rand_core::CryptoRngCore,
+        ) -> Result<(Ciphertext, SharedSecret), Error> {
+            let (ciphertext, secret) = self
+                .0
+                .encapsulate()
+                .map_err(|_| INVALID_KEY_SHARE)?;
+
+            let mut out = [0u8; MLKEM768_SECRET_LEN];
+            out.copy_from_slice(secret.as_ref());
+            Ok((
+                Ciphertext(ciphertext.as_ref().to_vec()),
+                SharedSecret(zeroize::Zeroizing::new(out)),
+            ))
+        }
+    }
+
+    impl EncapsulationKey {
+        /// Serializes this key to its fixed-length [`MLKEM768_ENCAP_LEN`](crate::MLKEM768_ENCAP_LEN)-byte encoding.
+        pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+            Ok(self
+                .0
+                .key_bytes()
+                .map_err(|_| Error::FailedToGetRandomBytes)?
+                .as_ref()
+                .to_vec())
+        }
+    }
+
+    impl TryFrom<&[u8]> for EncapsulationKey {
+        type Error = Error;
+
+        fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+            if bytes.len() != MLKEM768_ENCAP_LEN {
+                return Err(INVALID_KEY_SHARE);
+            }
+
+            aws_kem::EncapsulationKey::new(&ML_KEM_768, bytes)
+                .map(Self)
+                .map_err(|_| INVALID_KEY_SHARE)
+        }
+    }
+
+    impl Ciphertext {
+        /// Returns the fixed-length `MLKEM768_CIPHERTEXT_LEN`-byte encoding of this ciphertext.
+        pub fn as_bytes(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl TryFrom<&[u8]> for Ciphertext {
+        type Error = Error;
+
+        fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+            if bytes.len() != MLKEM768_CIPHERTEXT_LEN {
+                return Err(INVALID_KEY_SHARE);
+            }
+
+            Ok(Self(bytes.to_vec()))
+        }
+    }
+
+    impl SharedSecret {
+        /// Returns the raw bytes of this shared secret.
+        pub fn secret_bytes(&self) -> &[u8] {
+            self.0.as_slice()
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "ml-kem-pure")))]
+mod tests {
+    use super::*;
+
+    /// Runs a full initiator/responder handshake over `group` and asserts
+    /// both sides agree on the resulting shared secret.
+    fn round_trip(group: &dyn SupportedKxGroup) {
+        let initiator = group.start().unwrap();
+        let completed = group.start_and_complete(initiator.pub_key()).unwrap();
+        let initiator_secret = initiator.complete(&completed.pub_key).unwrap();
+
+        assert_eq!(
+            initiator_secret.secret_bytes(),
+            completed.secret.secret_bytes()
+        );
+    }
+
+    #[test]
+    fn secp256r1_mlkem768_round_trips() {
+        round_trip(&SecP256r1MLKEM768);
+    }
+
+    #[test]
+    fn secp384r1_mlkem1024_round_trips() {
+        round_trip(&SecP384r1MLKEM1024);
+    }
+
+    #[test]
+    fn xwing_round_trips() {
+        round_trip(&XWing);
+    }
+
+    #[test]
+    fn xwing_combine_is_deterministic() {
+        let ss_mlkem = [1u8; 32];
+        let ss_classical = [2u8; 32];
+        let ct_classical = [3u8; 32];
+        let pk_classical = [4u8; 32];
+
+        let a = xwing_combine(&ss_mlkem, &ss_classical, &ct_classical, &pk_classical);
+        let b = xwing_combine(&ss_mlkem, &ss_classical, &ct_classical, &pk_classical);
+        assert_eq!(a.0, b.0);
+    }
+
+    #[test]
+    fn xwing_combine_is_sensitive_to_ciphertext_and_pubkey_order() {
+        let ss_mlkem = [1u8; 32];
+        let ss_classical = [2u8; 32];
+        let ct_classical = [3u8; 32];
+        let pk_classical = [4u8; 32];
+
+        let baseline = xwing_combine(&ss_mlkem, &ss_classical, &ct_classical, &pk_classical);
+
+        // Swapping `ct_classical` and `pk_classical` must change the
+        // output -- this is the exact byte-order mistake (e.g. binding the
+        // responder's ciphertext where the initiator's public key belongs)
+        // that real X-Wing interop depends on not making.
+        let swapped = xwing_combine(&ss_mlkem, &ss_classical, &pk_classical, &ct_classical);
+        assert_ne!(baseline.0, swapped.0);
+    }
+
+    #[test]
+    fn x25519_mlkem768_round_trips_as_a_hybrid_kx_group() {
+        round_trip(&X25519MLKEM768);
+    }
+
+    #[test]
+    fn mlkem768_standalone_round_trips() {
+        use ::kem::{Decapsulate, Encapsulate};
+
+        let decap_key = mlkem768::DecapsulationKey::generate().unwrap();
+        let encap_key = decap_key.encapsulation_key().unwrap();
+
+        let (ciphertext, encap_secret) = encap_key.encapsulate(&mut rand_core::OsRng).unwrap();
+        let decap_secret = decap_key.decapsulate(&ciphertext).unwrap();
+
+        assert_eq!(encap_secret.secret_bytes(), decap_secret.secret_bytes());
+    }
+
+    #[test]
+    fn mlkem768_standalone_bytes_round_trip() {
+        use ::kem::{Decapsulate, Encapsulate};
+
+        let decap_key = mlkem768::DecapsulationKey::generate().unwrap();
+        let encap_key = decap_key.encapsulation_key().unwrap();
+
+        let encap_bytes = encap_key.to_bytes().unwrap();
+        let decoded_encap_key = mlkem768::EncapsulationKey::try_from(encap_bytes.as_slice())
+            .unwrap();
+
+        let (ciphertext, secret) = decoded_encap_key
+            .encapsulate(&mut rand_core::OsRng)
+            .unwrap();
+        let ciphertext_bytes = ciphertext.as_bytes().to_vec();
+        let decoded_ciphertext =
+            mlkem768::Ciphertext::try_from(ciphertext_bytes.as_slice()).unwrap();
+
+        let decap_secret = decap_key.decapsulate(&decoded_ciphertext).unwrap();
+        assert_eq!(secret.secret_bytes(), decap_secret.secret_bytes());
+    }
+}